@@ -0,0 +1,140 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{
+    io::SeekFrom,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::io::{AsyncRead, AsyncSeek, AsyncWrite};
+
+use crate::{Result, SeekMode};
+
+/// This allows for an async callback stream over the Uniffi async interface.
+///
+/// Mirrors [`crate::Stream`], but lets a foreign implementation backed by a
+/// network socket (e.g. fetching a remote asset over HTTP) yield to the
+/// runtime instead of blocking the calling thread.
+#[uniffi::export(with_foreign)]
+#[async_trait::async_trait]
+pub trait AsyncStream: Send + Sync {
+    /// Read a stream of bytes from the stream
+    async fn read_stream(&self, length: u64) -> Result<Vec<u8>>;
+    /// Seek to a position in the stream
+    async fn seek_stream(&self, pos: i64, mode: SeekMode) -> Result<u64>;
+    /// Write a stream of bytes to the stream
+    async fn write_stream(&self, data: Vec<u8>) -> Result<u64>;
+}
+
+/// Adapts an [`AsyncStream`] callback into the `futures` async I/O traits the
+/// c2pa SDK's `async_generic` entry points expect
+pub struct AsyncStreamAdapter<'a> {
+    stream: &'a dyn AsyncStream,
+    pending_read: Option<Pin<Box<dyn std::future::Future<Output = Result<Vec<u8>>> + Send + 'a>>>,
+    pending_seek: Option<Pin<Box<dyn std::future::Future<Output = Result<u64>> + Send + 'a>>>,
+    pending_write: Option<Pin<Box<dyn std::future::Future<Output = Result<u64>> + Send + 'a>>>,
+}
+
+impl<'a> AsyncStreamAdapter<'a> {
+    pub fn from_stream(stream: &'a dyn AsyncStream) -> Self {
+        Self {
+            stream,
+            pending_read: None,
+            pending_seek: None,
+            pending_write: None,
+        }
+    }
+
+    fn io_err(err: crate::Error) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::Other, err)
+    }
+}
+
+impl<'a> AsyncRead for AsyncStreamAdapter<'a> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let len = buf.len() as u64;
+        let stream = self.stream;
+        let fut = self
+            .pending_read
+            .get_or_insert_with(|| Box::pin(async move { stream.read_stream(len).await }));
+        match fut.as_mut().poll(cx) {
+            Poll::Ready(result) => {
+                self.pending_read = None;
+                let bytes = result.map_err(Self::io_err)?;
+                let n = bytes.len();
+                buf[..n].copy_from_slice(&bytes);
+                Poll::Ready(Ok(n))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<'a> AsyncSeek for AsyncStreamAdapter<'a> {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<std::io::Result<u64>> {
+        let (pos, mode) = match pos {
+            SeekFrom::Current(pos) => (pos, SeekMode::Current),
+            SeekFrom::Start(pos) => (pos as i64, SeekMode::Start),
+            SeekFrom::End(pos) => (pos, SeekMode::End),
+        };
+        let stream = self.stream;
+        let fut = self
+            .pending_seek
+            .get_or_insert_with(|| Box::pin(async move { stream.seek_stream(pos, mode).await }));
+        match fut.as_mut().poll(cx) {
+            Poll::Ready(result) => {
+                self.pending_seek = None;
+                Poll::Ready(result.map_err(Self::io_err))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<'a> AsyncWrite for AsyncStreamAdapter<'a> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let data = buf.to_vec();
+        let stream = self.stream;
+        let fut = self
+            .pending_write
+            .get_or_insert_with(|| Box::pin(async move { stream.write_stream(data).await }));
+        match fut.as_mut().poll(cx) {
+            Poll::Ready(result) => {
+                self.pending_write = None;
+                let n = result.map_err(Self::io_err)?;
+                Poll::Ready(Ok(n as usize))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
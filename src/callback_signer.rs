@@ -34,6 +34,13 @@ pub struct RemoteSigner {
     reserve_size: u32,
 }
 
+/// Worst-case DER signature length plus COSE structural overhead for a given
+/// signing algorithm, used as the default reserve size when the caller has no
+/// chain to introspect
+fn default_reserve_size(alg: SigningAlg) -> u32 {
+    (crate::reserve_size::signature_len(alg) + crate::reserve_size::COSE_STRUCTURAL_OVERHEAD) as u32
+}
+
 impl c2pa::Signer for RemoteSigner {
   fn alg(&self) -> SigningAlg {
       self.alg
@@ -82,18 +89,38 @@ impl CallbackSigner {
     pub fn new_from_signer(
       callback: Box<dyn SignerCallback>,
       alg: SigningAlg,
-      reserve_size: u32,
+      reserve_size: Option<u32>,
     ) -> Self {
         debug!("c2pa-python: CallbackSigner -> new_from_signer");
         let signer = RemoteSigner {
             signer_callback: callback,
             alg,
-            reserve_size
+            reserve_size: reserve_size.unwrap_or_else(|| default_reserve_size(alg)),
         };
 
         Self { signer: Box::new(signer) }
     }
 
+    /// Wraps a keyless [`crate::SigstoreSigner`] so it can be handed to `Builder::sign`
+    /// like any other signer
+    #[cfg(feature = "v1")]
+    pub fn new_from_sigstore(signer: crate::signer_info::SigstoreSigner) -> Self {
+        Self { signer: Box::new(signer) }
+    }
+
+    /// Builds a signer from a [`crate::SignerConfig`], so its OCSP stapling, SCT
+    /// embedding, trust-anchor/EKU validation, and computed `reserve_size` are
+    /// reachable from `Builder::sign`/`sign_async`/`sign_file` like any other signer
+    #[cfg(feature = "v1")]
+    pub fn new_from_config(
+        callback: Box<dyn SignerCallback>,
+        config: crate::signer_info::SignerConfig,
+    ) -> Self {
+        Self {
+            signer: Box::new(crate::signer_info::CallbackSigner::new(callback, config)),
+        }
+    }
+
     /// The python Builder wrapper sign function calls this
     pub fn signer(&self) -> &Box<dyn c2pa::Signer + Sync + Send> {
         &self.signer
@@ -0,0 +1,181 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Validates a signing cert chain against a trust-anchor bundle and C2PA's
+//! Extended Key Usage / basic-constraints rules before it is used to sign,
+//! so a misconfigured signing cert is caught at sign time rather than after
+//! a manifest has been distributed and downstream verifiers reject it.
+
+use x509_parser::extensions::ExtendedKeyUsage;
+use x509_parser::prelude::*;
+
+use crate::{Error, Result};
+
+/// Validates `chain` (end-entity cert first, root last, all DER-encoded) against
+/// `trust_anchors` (a PEM bundle, or `None` to skip anchor validation) and
+/// `allowed_eku_oids` (dotted-decimal OIDs; the end-entity cert must carry at
+/// least one)
+pub(crate) fn validate_signing_chain(
+    chain: &[Vec<u8>],
+    trust_anchors: Option<&[u8]>,
+    allowed_eku_oids: &[String],
+) -> Result<()> {
+    if chain.is_empty() {
+        return Err(Error::CertificateChain {
+            reason: "empty certificate chain".to_string(),
+        });
+    }
+
+    let certs: Vec<X509Certificate> = chain
+        .iter()
+        .enumerate()
+        .map(|(depth, der)| {
+            X509Certificate::from_der(der)
+                .map(|(_, cert)| cert)
+                .map_err(|e| Error::CertificateChain {
+                    reason: format!("failed to parse certificate at depth {depth}: {e}"),
+                })
+        })
+        .collect::<Result<_>>()?;
+    let leaf = &certs[0];
+
+    if !leaf.validity().is_valid() {
+        return Err(Error::CertificateChain {
+            reason: "signing cert is expired or not yet valid".to_string(),
+        });
+    }
+
+    if matches!(leaf.basic_constraints(), Ok(Some(bc)) if bc.value.ca) {
+        return Err(Error::CertificateChain {
+            reason: "signing cert is a CA cert, not an end-entity cert".to_string(),
+        });
+    }
+
+    if !allowed_eku_oids.is_empty() {
+        let eku: Option<ExtendedKeyUsage> = leaf
+            .extended_key_usage()
+            .map_err(|e| Error::CertificateChain {
+                reason: format!("failed to parse EKU extension: {e}"),
+            })?
+            .map(|ext| ext.value.clone());
+        let has_allowed_eku = match &eku {
+            Some(eku) => eku
+                .other
+                .iter()
+                .any(|oid| allowed_eku_oids.iter().any(|allowed| oid.to_id_string() == *allowed)),
+            None => false,
+        };
+        if !has_allowed_eku {
+            return Err(Error::CertificateChain {
+                reason: "signing cert does not carry an acceptable Extended Key Usage OID"
+                    .to_string(),
+            });
+        }
+    }
+
+    if leaf.issuer() == leaf.subject() {
+        return Err(Error::CertificateChain {
+            reason: "signing cert is self-signed".to_string(),
+        });
+    }
+
+    // A cert used to sign C2PA manifests must assert it may be used to produce
+    // signatures; a key usage extension that's present but omits digitalSignature
+    // means the CA never intended this key for that purpose.
+    if let Some(key_usage) = leaf.key_usage().map_err(|e| Error::CertificateChain {
+        reason: format!("failed to parse key usage extension: {e}"),
+    })? {
+        if !key_usage.value.digital_signature() {
+            return Err(Error::CertificateChain {
+                reason: "signing cert's key usage does not permit digital signatures".to_string(),
+            });
+        }
+    }
+
+    for (depth, cert) in certs.iter().enumerate().skip(1) {
+        match cert.basic_constraints() {
+            Ok(Some(bc)) if bc.value.ca => {
+                if let Some(pathlen) = bc.value.path_len_constraint {
+                    // pathlen bounds the intermediate CA certs between this
+                    // cert and the end-entity cert at depth 0
+                    let intermediates_below = depth - 1;
+                    if (intermediates_below as u32) > pathlen {
+                        return Err(Error::CertificateChain {
+                            reason: format!(
+                                "certificate chain exceeds pathlen constraint at depth {depth}"
+                            ),
+                        });
+                    }
+                }
+            }
+            _ => {
+                return Err(Error::CertificateChain {
+                    reason: format!("intermediate cert at depth {depth} is not a valid CA cert"),
+                });
+            }
+        }
+    }
+
+    // Each cert in the chain must be cryptographically signed by the next one up,
+    // not merely carry a matching issuer/subject DN pair (which an attacker-forged
+    // cert could trivially spoof).
+    for depth in 0..certs.len() - 1 {
+        let (subject, issuer) = (&certs[depth], &certs[depth + 1]);
+        subject
+            .verify_signature(Some(issuer.public_key()))
+            .map_err(|e| Error::CertificateChain {
+                reason: format!(
+                    "certificate at depth {depth} was not signed by its claimed issuer at depth {}: {e}",
+                    depth + 1
+                ),
+            })?;
+    }
+
+    if let Some(trust_anchors) = trust_anchors {
+        let root_der = chain.last().ok_or_else(|| Error::CertificateChain {
+            reason: "empty certificate chain".to_string(),
+        })?;
+        let root = certs.last().ok_or_else(|| Error::CertificateChain {
+            reason: "empty certificate chain".to_string(),
+        })?;
+        let anchors = pem::parse_many(trust_anchors).map_err(|e| Error::CertificateChain {
+            reason: format!("failed to parse trust anchor bundle: {e}"),
+        })?;
+        let trusted = anchors.iter().any(|anchor| {
+            // The root is directly a trust anchor, or a trust anchor's key
+            // cryptographically signed it (a cross-signed or issued root).
+            anchor.contents() == root_der.as_slice()
+                || X509Certificate::from_der(anchor.contents())
+                    .map(|(_, anchor_cert)| root.verify_signature(Some(anchor_cert.public_key())).is_ok())
+                    .unwrap_or(false)
+        });
+        if !trusted {
+            return Err(Error::CertificateChain {
+                reason: "certificate chain does not chain to a configured trust anchor (unknown issuer)"
+                    .to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_chain() {
+        let err = validate_signing_chain(&[], None, &[]).unwrap_err();
+        assert!(matches!(err, Error::CertificateChain { reason } if reason.contains("empty")));
+    }
+}
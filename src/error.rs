@@ -7,7 +7,10 @@ pub enum Error {
     #[error("Assertion {reason}")]
     Assertion { reason: String },
     #[error("AssertionNotFound {reason}")]
-    AssertionNotFound { reason: String },
+    AssertionNotFound {
+        reason: String,
+        url: Option<String>,
+    },
     #[error("Decoding {reason}")]
     Decoding { reason: String },
     #[error("Encoding {reason}")]
@@ -21,7 +24,10 @@ pub enum Error {
     #[error("Manifest {reason}")]
     Manifest { reason: String },
     #[error("ManifestNotFound {reason}")]
-    ManifestNotFound { reason: String },
+    ManifestNotFound {
+        reason: String,
+        label: Option<String>,
+    },
     #[error("NotSupported {reason}")]
     NotSupported { reason: String },
     #[error("Other {reason}")]
@@ -32,6 +38,10 @@ pub enum Error {
     ResourceNotFound { reason: String },
     #[error("RwLock")]
     RwLock,
+    #[error("CtLogNotTrusted {log_id}")]
+    CtLogNotTrusted { log_id: String },
+    #[error("CertificateChain {reason}")]
+    CertificateChain { reason: String },
     #[error("Signature {reason}")]
     Signature { reason: String },
     #[error("Verify {reason}")]
@@ -46,7 +56,8 @@ impl Error {
         let err_str = err.to_string();
         match err {
             c2pa::Error::AssertionMissing { url } => Self::AssertionNotFound {
-                reason: "".to_string(),
+                reason: err_str,
+                url: Some(url),
             },
             AssertionInvalidRedaction
             | AssertionRedactionNotFound
@@ -62,7 +73,10 @@ impl Error {
             | ClaimDisallowedRedaction
             | UpdateManifestInvalid
             | TooManyManifestStores => Self::Manifest { reason: err_str },
-            ClaimMissing { label } => Self::ManifestNotFound { reason: err_str },
+            ClaimMissing { label } => Self::ManifestNotFound {
+                reason: err_str,
+                label: Some(label),
+            },
             AssertionDecoding(_) | ClaimDecoding => Self::Decoding { reason: err_str },
             AssertionEncoding | XmlWriteError | ClaimEncoding => Self::Encoding { reason: err_str },
             InvalidCoseSignature { coset_error } => Self::Signature { reason: err_str },
@@ -84,7 +98,10 @@ impl Error {
             RemoteManifestFetch(_) | RemoteManifestUrl(_) => {
                 Self::RemoteManifest { reason: err_str }
             }
-            JumbfNotFound => Self::ManifestNotFound { reason: err_str },
+            JumbfNotFound => Self::ManifestNotFound {
+                reason: err_str,
+                label: None,
+            },
             BadParam(_) | MissingFeature(_) => Self::Other { reason: err_str },
             IoError(_) => Self::Io { reason: err_str },
             JsonError(e) => Self::Json { reason: err_str },
@@ -12,6 +12,7 @@
 
 /// This module exports a C2PA library
 use std::env;
+use std::io::Write;
 use std::sync::RwLock;
 
 pub use c2pa::SigningAlg;
@@ -26,11 +27,21 @@ pub use json_api::{read_file, read_ingredient_file, sign_file};
 #[cfg(feature = "v1")]
 mod signer_info;
 #[cfg(feature = "v1")]
-pub use signer_info::{CallbackSigner, SignerCallback, SignerConfig, SignerInfo};
+pub use signer_info::{SignerConfig, SignerInfo, SigstoreConfig, SigstoreSigner, TransparencyLogEntry};
 mod callback_signer;
 pub use callback_signer::{CallbackSigner, SignerCallback};
 mod streams;
 pub use streams::{SeekMode, Stream, StreamAdapter};
+mod settings;
+pub use settings::{
+    load_settings, load_settings_from_stream, set_trust_anchors, set_verify_remote_manifests,
+};
+mod async_streams;
+pub use async_streams::{AsyncStream, AsyncStreamAdapter};
+mod sct;
+pub use sct::{CtLogKeyring, SignedCertificateTimestamp, strip_precert_extensions, verify_scts};
+mod cert_policy;
+mod reserve_size;
 
 #[cfg(test)]
 mod test_stream;
@@ -53,6 +64,63 @@ pub fn sdk_version() -> String {
     )
 }
 
+/// A typed report of this build's version and supported formats
+///
+/// Supersedes parsing [`sdk_version`]'s free-form string for feature detection
+/// (e.g. "can I sign `image/avif` with this build?").
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// Version of this crate, e.g. `0.5.0`
+    pub crate_version: String,
+    /// Version of the underlying c2pa-rs SDK
+    pub sdk_version: String,
+    /// C2PA claim/protocol version this build targets
+    pub claim_version: String,
+    /// MIME types this build can read (parse and validate a manifest store from)
+    pub readable_formats: Vec<String>,
+    /// MIME types this build can write (embed a signed manifest into)
+    pub writable_formats: Vec<String>,
+}
+
+/// Returns a structured report of this build's version and supported formats
+pub fn capabilities() -> Capabilities {
+    let formats: Vec<String> = c2pa::supported_extensions_and_mime_types()
+        .into_iter()
+        .map(|(_ext, mime)| mime.to_string())
+        .collect();
+    Capabilities {
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        sdk_version: c2pa::VERSION.to_string(),
+        claim_version: c2pa::CLAIM_SPECIFICATION_VERSION.to_string(),
+        readable_formats: formats.clone(),
+        writable_formats: formats,
+    }
+}
+
+/// A single entry from the manifest store's validation result set
+///
+/// Mirrors the c2pa SDK's internal status tracker so foreign callers can
+/// branch on specific failure codes (e.g. `signingCredential.untrusted`)
+/// instead of string-matching a flattened `Error` reason.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct ValidationStatus {
+    pub code: String,
+    pub url: Option<String>,
+    pub explanation: Option<String>,
+}
+
+impl From<&c2pa::ValidationStatus> for ValidationStatus {
+    fn from(status: &c2pa::ValidationStatus) -> Self {
+        Self {
+            code: status.code().to_string(),
+            url: status.url().map(|u| u.to_string()),
+            explanation: status.explanation().map(|e| e.to_string()),
+        }
+    }
+}
+
 pub struct Reader {
     reader: RwLock<c2pa::Reader>,
 }
@@ -64,6 +132,10 @@ impl Reader {
         }
     }
 
+    /// Reads a manifest store from the given stream
+    ///
+    /// Honors any trust anchors and verification policy previously applied via
+    /// [`load_settings`]/[`load_settings_from_stream`].
     pub fn from_stream(&self, format: &str, stream: &dyn Stream) -> Result<String> {
         // uniffi doesn't allow mutable parameters, so we we use an adapter
         let mut stream = StreamAdapter::from(stream);
@@ -85,6 +157,45 @@ impl Reader {
         }
     }
 
+    /// Returns the validation status entries produced while reading the manifest store
+    ///
+    /// Each entry carries the SDK's status `code`, an optional `url` pointing at the
+    /// offending assertion or manifest, and an optional human-readable `explanation`.
+    pub fn validation_statuses(&self) -> Result<Vec<ValidationStatus>> {
+        if let Ok(st) = self.reader.try_read() {
+            Ok(st
+                .validation_status()
+                .unwrap_or_default()
+                .iter()
+                .map(ValidationStatus::from)
+                .collect())
+        } else {
+            Err(Error::RwLock)
+        }
+    }
+
+    /// Returns true if there are no validation status entries, i.e. the manifest store
+    /// passed validation with no reported problems
+    pub fn is_valid(&self) -> Result<bool> {
+        Ok(self.validation_statuses()?.is_empty())
+    }
+
+    /// Reads a manifest store from the given async stream without buffering it in full
+    ///
+    /// Intended for network-backed sources (e.g. fetching an asset over HTTP) where the
+    /// caller would otherwise have to download the whole file before validation can start.
+    pub async fn from_stream_async(&self, format: &str, stream: &dyn AsyncStream) -> Result<String> {
+        let mut stream = AsyncStreamAdapter::from_stream(stream);
+        let reader = c2pa::Reader::from_stream_async(format, &mut stream).await?;
+        let json = reader.to_string();
+        if let Ok(mut st) = self.reader.try_write() {
+            *st = reader;
+        } else {
+            return Err(Error::RwLock);
+        };
+        Ok(json)
+    }
+
     pub fn resource_to_stream(&self, uri: &str, stream: &dyn Stream) -> Result<u64> {
         if let Ok(reader) = self.reader.try_read() {
             let mut stream = StreamAdapter::from(stream);
@@ -96,9 +207,65 @@ impl Reader {
     }
 }
 
+/// Holds the inner `c2pa::Builder` taken out of `Builder::builder`'s lock for
+/// the duration of an async operation, restoring it on drop
+///
+/// This is what lets `Builder::sign_async` avoid holding the write lock across
+/// its `.await`: the builder is moved out into this guard instead. `Drop`
+/// puts it back unconditionally, whether the guard goes out of scope because
+/// the async call finished normally or because the enclosing future was
+/// dropped mid-poll (a timeout, `select!`, or cancellation crossing the
+/// uniffi async bridge) - so the slot is never left permanently `None`.
+struct TakenBuilder<'a> {
+    lock: &'a RwLock<Option<c2pa::Builder>>,
+    builder: Option<c2pa::Builder>,
+}
+
+impl<'a> TakenBuilder<'a> {
+    fn take(lock: &'a RwLock<Option<c2pa::Builder>>) -> Result<Self> {
+        let mut guard = lock.try_write().map_err(|_| Error::RwLock)?;
+        let builder = guard.take().ok_or(Error::RwLock)?;
+        Ok(Self {
+            lock,
+            builder: Some(builder),
+        })
+    }
+}
+
+impl std::ops::Deref for TakenBuilder<'_> {
+    type Target = c2pa::Builder;
+
+    fn deref(&self) -> &c2pa::Builder {
+        self.builder.as_ref().expect("builder is only taken once, for the guard's lifetime")
+    }
+}
+
+impl std::ops::DerefMut for TakenBuilder<'_> {
+    fn deref_mut(&mut self) -> &mut c2pa::Builder {
+        self.builder.as_mut().expect("builder is only taken once, for the guard's lifetime")
+    }
+}
+
+impl Drop for TakenBuilder<'_> {
+    fn drop(&mut self) {
+        if let Some(builder) = self.builder.take() {
+            // Any other call holding the lock right now finds the slot `None`
+            // and returns `Error::RwLock` at once, so this wait is brief.
+            let mut guard = self
+                .lock
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            *guard = Some(builder);
+        }
+    }
+}
+
 pub struct Builder {
-    // The RwLock is needed because uniffi doesn't allow a mutable self parameter
-    builder: RwLock<c2pa::Builder>,
+    // The RwLock is needed because uniffi doesn't allow a mutable self parameter.
+    // `None` only ever appears momentarily, while `sign_async` has taken
+    // ownership of the inner builder (via `TakenBuilder`) to run its `.await`
+    // outside the lock; see `with_builder` and `sign_async` below.
+    builder: RwLock<Option<c2pa::Builder>>,
 }
 
 impl Builder {
@@ -107,29 +274,61 @@ impl Builder {
     /// Uniffi does not support constructors that return errors
     pub fn new() -> Self {
         Self {
-            builder: RwLock::new(c2pa::Builder::default()),
+            builder: RwLock::new(Some(c2pa::Builder::default())),
         }
     }
 
+    /// Runs `f` against the inner builder, failing fast with [`Error::RwLock`]
+    /// if it's already locked or momentarily taken by an in-flight
+    /// [`Self::sign_async`] call
+    fn with_builder<R>(&self, f: impl FnOnce(&mut c2pa::Builder) -> Result<R>) -> Result<R> {
+        let mut guard = self.builder.try_write().map_err(|_| Error::RwLock)?;
+        let builder = guard.as_mut().ok_or(Error::RwLock)?;
+        f(builder)
+    }
+
     /// Create a new builder using the Json manifest definition
     pub fn with_json(&self, json: &str) -> Result<()> {
-        if let Ok(mut builder) = self.builder.try_write() {
+        self.with_builder(|builder| {
             *builder = c2pa::Builder::from_json(json)?;
-        } else {
-            return Err(Error::RwLock);
-        };
-        Ok(())
+            Ok(())
+        })
+    }
+
+    /// Add a JSON assertion to the manifest's assertion store
+    pub fn add_assertion(&self, label: &str, json: &str) -> Result<()> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|e| Error::Json {
+            reason: e.to_string(),
+        })?;
+        self.with_builder(|builder| Ok(builder.add_assertion(label, &value)?))
+    }
+
+    /// Add a CBOR-encoded assertion to the manifest's assertion store
+    pub fn add_cbor_assertion(&self, label: &str, cbor: Vec<u8>) -> Result<()> {
+        self.with_builder(|builder| Ok(builder.add_assertion_cbor(label, &cbor)?))
+    }
+
+    /// Set an explicit thumbnail for the manifest, read from the given stream
+    pub fn set_thumbnail(&self, format: &str, stream: &dyn Stream) -> Result<()> {
+        let mut stream = StreamAdapter::from(stream);
+        self.with_builder(|builder| Ok(builder.set_thumbnail(format, &mut stream)?))
+    }
+
+    /// Toggle whether `sign` generates a claim thumbnail from the source asset
+    ///
+    /// Enabled by default in the underlying SDK; disable this when the caller has
+    /// already supplied one via `set_thumbnail`.
+    pub fn enable_auto_thumbnails(&self, enabled: bool) -> Result<()> {
+        self.with_builder(|builder| {
+            builder.set_no_thumbnail(!enabled);
+            Ok(())
+        })
     }
 
     /// Add a resource to the builder
     pub fn add_resource(&self, uri: &str, stream: &dyn Stream) -> Result<()> {
-        if let Ok(mut builder) = self.builder.try_write() {
-            let mut stream = StreamAdapter::from(stream);
-            builder.add_resource(uri, &mut stream)?;
-        } else {
-            return Err(Error::RwLock);
-        };
-        Ok(())
+        let mut stream = StreamAdapter::from(stream);
+        self.with_builder(|builder| Ok(builder.add_resource(uri, &mut stream)?))
     }
 
     pub fn add_ingredient(
@@ -138,38 +337,31 @@ impl Builder {
         format: &str,
         stream: &dyn Stream,
     ) -> Result<()> {
-        if let Ok(mut builder) = self.builder.try_write() {
-            let mut stream = StreamAdapter::from(stream);
-            builder.add_ingredient_from_stream(ingredient_json, format, &mut stream)?;
-        } else {
-            return Err(Error::RwLock);
-        };
-        Ok(())
+        let mut stream = StreamAdapter::from(stream);
+        self.with_builder(|builder| {
+            Ok(builder.add_ingredient_from_stream(ingredient_json, format, &mut stream)?)
+        })
     }
 
     /// Write the builder to the destination stream as an archive
     pub fn to_archive(&self, dest: &dyn Stream) -> Result<()> {
-        if let Ok(mut builder) = self.builder.try_write() {
-            let mut dest = StreamAdapter::from(dest);
-            builder.to_archive(&mut dest)?;
-        } else {
-            return Err(Error::RwLock);
-        };
-        Ok(())
+        let mut dest = StreamAdapter::from(dest);
+        self.with_builder(|builder| Ok(builder.to_archive(&mut dest)?))
     }
 
     /// Create a new builder from an archive
     pub fn from_archive(&self, source: &dyn Stream) -> Result<()> {
-        if let Ok(mut builder) = self.builder.try_write() {
-            let mut source = StreamAdapter::from(source);
+        let mut source = StreamAdapter::from(source);
+        self.with_builder(|builder| {
             *builder = c2pa::Builder::from_archive(&mut source)?;
-        } else {
-            return Err(Error::RwLock);
-        };
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Sign an asset and write the result to the destination stream
+    ///
+    /// Honors any trust anchors and verification policy previously applied via
+    /// [`load_settings`]/[`load_settings_from_stream`].
     pub fn sign(
         &self,
         signer: &CallbackSigner,
@@ -180,21 +372,83 @@ impl Builder {
         // uniffi doesn't allow mutable parameters, so we we use an adapter
         let mut source = StreamAdapter::from(source);
         let mut dest = StreamAdapter::from(dest);
-        if let Ok(mut builder) = self.builder.try_write() {
-            let signer = (*signer).signer();
-            Ok(builder.sign(signer, format, &mut source, &mut dest)?)
-        } else {
-            Err(Error::RwLock)
-        }
+        let signer = (*signer).signer();
+        self.with_builder(|builder| Ok(builder.sign(signer, format, &mut source, &mut dest)?))
+    }
+
+    /// Sign an asset read from an async source and write the result to an async destination
+    ///
+    /// Lets large remote assets be streamed and signed without buffering the whole
+    /// file in memory first.
+    pub async fn sign_async(
+        &self,
+        signer: &CallbackSigner,
+        format: &str,
+        source: &dyn AsyncStream,
+        dest: &dyn AsyncStream,
+    ) -> Result<Vec<u8>> {
+        let mut source = AsyncStreamAdapter::from_stream(source);
+        let mut dest = AsyncStreamAdapter::from_stream(dest);
+
+        // Taking the builder out of the lock, rather than holding a write guard,
+        // keeps it from blocking every other `Builder` call for the duration of
+        // the I/O below. `TakenBuilder::drop` restores it unconditionally - on
+        // normal return *and* if this future is dropped mid-`.await` (a timeout,
+        // `select!`, or cancellation crossing the uniffi async bridge) - so the
+        // slot is never left permanently `None`.
+        let mut builder = TakenBuilder::take(&self.builder)?;
+        let signer = (*signer).signer();
+        let result = builder
+            .sign_async(signer, format, &mut source, &mut dest)
+            .await;
+
+        Ok(result?)
+    }
+
+    /// Set a remote manifest URL reference on the manifest being built
+    ///
+    /// When `embed_ref` is false, the asset is left unmodified apart from the
+    /// remote-URL pointer; pair this with [`Self::sign_sidecar`] to produce a
+    /// detached `.c2pa` manifest for cloud-hosted provenance.
+    pub fn set_remote_url(&self, url: &str, embed_ref: bool) -> Result<()> {
+        self.with_builder(|builder| {
+            builder.set_remote_url(url);
+            builder.set_no_embed(!embed_ref);
+            Ok(())
+        })
+    }
+
+    /// Sign an asset, writing the detached manifest store to `manifest_dest` in
+    /// addition to `dest`
+    ///
+    /// `dest` receives exactly what plain [`Self::sign`] would have produced: by
+    /// default the asset with the manifest store embedded; after
+    /// [`Self::set_remote_url`], the asset also carries the remote-URL reference,
+    /// and with `embed_ref: false` the manifest store is not embedded in it.
+    /// `manifest_dest` additionally receives the detached manifest store bytes,
+    /// which are also returned, for upload to cloud storage.
+    pub fn sign_sidecar(
+        &self,
+        signer: &CallbackSigner,
+        format: &str,
+        source: &dyn Stream,
+        dest: &dyn Stream,
+        manifest_dest: &dyn Stream,
+    ) -> Result<Vec<u8>> {
+        let mut source = StreamAdapter::from(source);
+        let mut dest = StreamAdapter::from(dest);
+        let mut manifest_dest = StreamAdapter::from(manifest_dest);
+        let signer = (*signer).signer();
+        self.with_builder(|builder| {
+            let manifest_bytes = builder.sign(signer, format, &mut source, &mut dest)?;
+            manifest_dest.write_all(&manifest_bytes)?;
+            Ok(manifest_bytes)
+        })
     }
 
     /// Sign an asset and write the result to the destination stream
     pub fn sign_file(&self, signer: &CallbackSigner, source: &str, dest: &str) -> Result<Vec<u8>> {
-        if let Ok(mut builder) = self.builder.try_write() {
-            let signer = (*signer).signer();
-            Ok(builder.sign_file(signer, source, dest)?)
-        } else {
-            Err(Error::RwLock)
-        }
+        let signer = (*signer).signer();
+        self.with_builder(|builder| Ok(builder.sign_file(signer, source, dest)?))
     }
 }
@@ -0,0 +1,36 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Signature-size estimates shared by the computed `reserve_size` of
+//! `callback_signer`'s `RemoteSigner` and `signer_info`'s `CallbackSigner`, so
+//! the two can't drift out of sync on the same per-algorithm numbers.
+//! `SigstoreSigner` reserves a fixed size and does not use them.
+
+use c2pa::SigningAlg;
+
+/// COSE structural overhead (headers, protected/unprotected attribute maps,
+/// CBOR tags) left unaccounted for by the signature and cert chain sizes alone
+pub(crate) const COSE_STRUCTURAL_OVERHEAD: usize = 1024;
+
+/// Worst-case DER signature length for a given signing algorithm
+pub(crate) fn signature_len(alg: SigningAlg) -> usize {
+    match alg {
+        SigningAlg::Es256 => 72,
+        SigningAlg::Es384 => 104,
+        SigningAlg::Es512 => 139,
+        SigningAlg::Ed25519 => 64,
+        // RSA signature lengths depend on key size, not algorithm; 512 covers
+        // up to a 4096-bit key with room for DER overhead.
+        SigningAlg::Ps256 | SigningAlg::Ps384 | SigningAlg::Ps512 => 512,
+        _ => 512,
+    }
+}
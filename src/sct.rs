@@ -0,0 +1,423 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::collections::{HashMap, HashSet};
+
+use sha2::Digest;
+
+use crate::{Error, Result};
+
+/// A Signed Certificate Timestamp (RFC 6962), proving a signing cert was
+/// submitted to a Certificate Transparency log
+///
+/// May be pre-embedded in the cert via OID `1.3.6.1.4.1.11129.2.4.2`, or
+/// supplied separately alongside the signature.
+#[derive(Clone, Debug)]
+pub struct SignedCertificateTimestamp {
+    pub version: u8,
+    pub log_id: [u8; 32],
+    pub timestamp: u64,
+    pub extensions: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl SignedCertificateTimestamp {
+    /// Parses a single SCT out of its RFC 6962 wire encoding
+    pub fn parse(der: &[u8]) -> Result<Self> {
+        if der.len() < 1 + 32 + 8 + 2 {
+            return Err(Error::Decoding {
+                reason: "SCT shorter than its fixed-size fields".to_string(),
+            });
+        }
+        let version = der[0];
+        let mut log_id = [0u8; 32];
+        log_id.copy_from_slice(&der[1..33]);
+        let timestamp = u64::from_be_bytes(der[33..41].try_into().unwrap());
+        let ext_len = u16::from_be_bytes([der[41], der[42]]) as usize;
+        let ext_end = 43 + ext_len;
+        let extensions = der
+            .get(43..ext_end)
+            .ok_or_else(|| Error::Decoding {
+                reason: "SCT extensions length out of bounds".to_string(),
+            })?
+            .to_vec();
+        // digitally-signed: hash algorithm (1 byte), signature algorithm (1 byte),
+        // then the signature itself behind a 2-byte length prefix
+        let sig_header = der.get(ext_end..ext_end + 4).ok_or_else(|| Error::Decoding {
+            reason: "SCT signature header out of bounds".to_string(),
+        })?;
+        let sig_len = u16::from_be_bytes([sig_header[2], sig_header[3]]) as usize;
+        let signature = der
+            .get(ext_end + 4..ext_end + 4 + sig_len)
+            .ok_or_else(|| Error::Decoding {
+                reason: "SCT signature length out of bounds".to_string(),
+            })?
+            .to_vec();
+        Ok(Self {
+            version,
+            log_id,
+            timestamp,
+            extensions,
+            signature,
+        })
+    }
+}
+
+/// A configurable set of trusted CT logs, keyed by log ID
+///
+/// The log ID is the SHA-256 hash of the log's SubjectPublicKeyInfo, per RFC 6962.
+#[derive(Clone, Debug, Default)]
+pub struct CtLogKeyring {
+    logs: HashMap<[u8; 32], Vec<u8>>,
+}
+
+impl CtLogKeyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a trusted log's public key (DER-encoded SubjectPublicKeyInfo)
+    pub fn add_log(&mut self, public_key_der: Vec<u8>) {
+        let log_id: [u8; 32] = sha2::Sha256::digest(&public_key_der).into();
+        self.logs.insert(log_id, public_key_der);
+    }
+
+    fn key_for(&self, log_id: &[u8; 32]) -> Option<&[u8]> {
+        self.logs.get(log_id).map(|v| v.as_slice())
+    }
+}
+
+/// Strips the poison extension and embedded-SCT-list extension from a precert's
+/// TBSCertificate before CT hashing, per RFC 6962 §3.2
+///
+/// A precert submitted for SCTs is disqualified from being presented as-is: it
+/// carries the poison extension (OID `1.3.6.1.4.1.11129.2.4.3`) marking it
+/// un-issuable, and once SCTs are obtained the final cert embeds them under OID
+/// `1.3.6.1.4.1.11129.2.4.2` instead. Both extensions must be absent from the
+/// bytes hashed into the "digitally-signed" structure, or verification will
+/// never match what the log actually signed.
+pub fn strip_precert_extensions(tbs_der: &[u8]) -> Result<Vec<u8>> {
+    let tbs_tlv = only_top_level_tlv(tbs_der)?;
+    if tlv_tag(tbs_tlv) != DER_SEQUENCE {
+        return Err(Error::Decoding {
+            reason: "expected a TBSCertificate SEQUENCE".to_string(),
+        });
+    }
+    let mut fields = split_top_level_tlvs(tlv_content(tbs_tlv))?;
+
+    // `extensions` is always the final, and only context-tag-3, field when present.
+    let Some(extensions_field) = fields.last().copied() else {
+        return Ok(tbs_der.to_vec());
+    };
+    if tlv_tag(extensions_field) != EXTENSIONS_EXPLICIT_TAG {
+        return Ok(tbs_der.to_vec());
+    }
+    fields.pop();
+
+    let extensions_seq = only_top_level_tlv(tlv_content(extensions_field))?;
+    if tlv_tag(extensions_seq) != DER_SEQUENCE {
+        return Err(Error::Decoding {
+            reason: "malformed Extensions wrapper".to_string(),
+        });
+    }
+
+    let mut kept_extensions = Vec::new();
+    for extension in split_top_level_tlvs(tlv_content(extensions_seq))? {
+        if tlv_tag(extension) != DER_SEQUENCE {
+            return Err(Error::Decoding {
+                reason: "malformed Extension entry".to_string(),
+            });
+        }
+        let extension_fields = split_top_level_tlvs(tlv_content(extension))?;
+        let oid_tlv = extension_fields.first().ok_or_else(|| Error::Decoding {
+            reason: "Extension missing extnID".to_string(),
+        })?;
+        let oid = x509_parser::oid_registry::asn1_rs::Oid::new(tlv_content(oid_tlv).to_vec().into());
+        if oid == POISON_OID || oid == EMBEDDED_SCT_LIST_OID {
+            continue;
+        }
+        kept_extensions.push(*extension);
+    }
+
+    let new_extensions_seq = encode_tlv(DER_SEQUENCE, &kept_extensions.concat());
+    let new_extensions_field = encode_tlv(EXTENSIONS_EXPLICIT_TAG, &new_extensions_seq);
+
+    let mut new_tbs_content: Vec<u8> = fields.concat();
+    new_tbs_content.extend_from_slice(&new_extensions_field);
+    Ok(encode_tlv(DER_SEQUENCE, &new_tbs_content))
+}
+
+const DER_SEQUENCE: u8 = 0x30;
+/// `extensions [3] EXPLICIT Extensions OPTIONAL` in TBSCertificate
+const EXTENSIONS_EXPLICIT_TAG: u8 = 0xa3;
+
+const POISON_OID: x509_parser::oid_registry::Oid<'static> =
+    x509_parser::oid_registry::asn1_rs::oid!(1.3.6.1.4.1.11129.2.4.3);
+const EMBEDDED_SCT_LIST_OID: x509_parser::oid_registry::Oid<'static> =
+    x509_parser::oid_registry::asn1_rs::oid!(1.3.6.1.4.1.11129.2.4.2);
+
+/// Returns the length, in bytes, of the single definite-length DER TLV starting
+/// at the front of `data`
+fn tlv_total_len(data: &[u8]) -> Result<usize> {
+    let short_der = || Error::Decoding {
+        reason: "truncated DER TLV".to_string(),
+    };
+    if data.len() < 2 {
+        return Err(short_der());
+    }
+    let len_byte = data[1];
+    let (content_len, header_len) = if len_byte & 0x80 == 0 {
+        (len_byte as usize, 2)
+    } else {
+        let num_len_bytes = (len_byte & 0x7f) as usize;
+        let len_bytes = data.get(2..2 + num_len_bytes).ok_or_else(short_der)?;
+        let len = len_bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, 2 + num_len_bytes)
+    };
+    let total = header_len + content_len;
+    if data.len() < total {
+        return Err(short_der());
+    }
+    Ok(total)
+}
+
+/// Splits a byte string containing zero or more consecutive DER TLVs into the
+/// full byte range (tag + length + content) of each
+fn split_top_level_tlvs(data: &[u8]) -> Result<Vec<&[u8]>> {
+    let mut tlvs = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let len = tlv_total_len(rest)?;
+        tlvs.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+    Ok(tlvs)
+}
+
+/// Splits `data` and requires it to contain exactly one top-level TLV, returning it
+fn only_top_level_tlv(data: &[u8]) -> Result<&[u8]> {
+    let tlvs = split_top_level_tlvs(data)?;
+    match tlvs.as_slice() {
+        [tlv] => Ok(tlv),
+        _ => Err(Error::Decoding {
+            reason: format!("expected exactly one DER TLV, found {}", tlvs.len()),
+        }),
+    }
+}
+
+fn tlv_tag(tlv: &[u8]) -> u8 {
+    tlv[0]
+}
+
+/// Returns the content bytes of a TLV whose total length has already been validated
+/// by [`tlv_total_len`]
+fn tlv_content(tlv: &[u8]) -> &[u8] {
+    let len_byte = tlv[1];
+    let header_len = if len_byte & 0x80 == 0 {
+        2
+    } else {
+        2 + (len_byte & 0x7f) as usize
+    };
+    &tlv[header_len..]
+}
+
+pub(crate) fn encode_der_len(len: usize) -> Vec<u8> {
+    if len < 0x80 {
+        return vec![len as u8];
+    }
+    let mut bytes = Vec::new();
+    let mut n = len;
+    while n > 0 {
+        bytes.push((n & 0xff) as u8);
+        n >>= 8;
+    }
+    bytes.reverse();
+    let mut out = vec![0x80 | bytes.len() as u8];
+    out.extend(bytes);
+    out
+}
+
+pub(crate) fn encode_tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    out.extend(encode_der_len(content.len()));
+    out.extend_from_slice(content);
+    out
+}
+
+/// Reconstructs the CT "digitally-signed" structure an SCT's signature covers,
+/// and verifies the signature against the given log's public key
+fn verify_one(
+    sct: &SignedCertificateTimestamp,
+    cert_der: &[u8],
+    is_precert: bool,
+    keyring: &CtLogKeyring,
+) -> Result<()> {
+    let Some(log_key) = keyring.key_for(&sct.log_id) else {
+        return Err(Error::CtLogNotTrusted {
+            log_id: hex_encode(&sct.log_id),
+        });
+    };
+
+    // A precert's poison extension and any embedded-SCT-list extension are never
+    // part of what the CT log actually signed; the log hashes the stripped
+    // TBSCertificate, so we must match that exactly or every precert SCT fails.
+    let owned_cert_der;
+    let cert_der = if is_precert {
+        owned_cert_der = strip_precert_extensions(cert_der)?;
+        owned_cert_der.as_slice()
+    } else {
+        cert_der
+    };
+
+    // digitally-signed struct { version; signature_type; timestamp; entry_type;
+    //   (pre)cert DER; extensions } per RFC 6962 §3.2
+    let mut signed_data = Vec::new();
+    signed_data.push(sct.version);
+    signed_data.push(0); // signature_type = certificate_timestamp
+    signed_data.extend_from_slice(&sct.timestamp.to_be_bytes());
+    let entry_type: u16 = if is_precert { 1 } else { 0 };
+    signed_data.extend_from_slice(&entry_type.to_be_bytes());
+    let cert_len = (cert_der.len() as u32).to_be_bytes();
+    signed_data.extend_from_slice(&cert_len[1..]); // 24-bit length prefix
+    signed_data.extend_from_slice(cert_der);
+    signed_data.extend_from_slice(&(sct.extensions.len() as u16).to_be_bytes());
+    signed_data.extend_from_slice(&sct.extensions);
+
+    verify_signature(log_key, &signed_data, &sct.signature)
+}
+
+fn verify_signature(public_key_der: &[u8], message: &[u8], signature: &[u8]) -> Result<()> {
+    use p256::ecdsa::signature::Verifier;
+
+    if let Ok(verifying_key) = p256::ecdsa::VerifyingKey::from_public_key_der(public_key_der) {
+        let sig = p256::ecdsa::Signature::from_der(signature).map_err(|e| Error::Verify {
+            reason: format!("invalid SCT signature encoding: {e}"),
+        })?;
+        return verifying_key.verify(message, &sig).map_err(|_| Error::Verify {
+            reason: "SCT signature did not verify against the log's EC key".to_string(),
+        });
+    }
+
+    use rsa::pkcs1v15::VerifyingKey as RsaVerifyingKey;
+    use rsa::sha2::Sha256;
+    use rsa::signature::Verifier as RsaVerifier;
+    let rsa_key = rsa::RsaPublicKey::from_public_key_der(public_key_der).map_err(|e| {
+        Error::Verify {
+            reason: format!("unsupported CT log key type: {e}"),
+        }
+    })?;
+    let verifying_key = RsaVerifyingKey::<Sha256>::new(rsa_key);
+    let sig = rsa::pkcs1v15::Signature::try_from(signature).map_err(|e| Error::Verify {
+        reason: format!("invalid SCT signature encoding: {e}"),
+    })?;
+    verifying_key.verify(message, &sig).map_err(|_| Error::Verify {
+        reason: "SCT signature did not verify against the log's RSA key".to_string(),
+    })
+}
+
+/// Verifies that at least `min_distinct_logs` of `scts` are valid and issued by
+/// distinct trusted logs in `keyring`
+///
+/// An SCT whose `log_id` isn't in `keyring` is not counted. If too few SCTs verify
+/// and one of them came from such a log, the result is [`Error::CtLogNotTrusted`]
+/// rather than a generic verification failure.
+pub fn verify_scts(
+    scts: &[SignedCertificateTimestamp],
+    cert_der: &[u8],
+    is_precert: bool,
+    keyring: &CtLogKeyring,
+    min_distinct_logs: usize,
+) -> Result<()> {
+    let mut verified_logs = HashSet::new();
+    let mut untrusted_log_id = None;
+    for sct in scts {
+        match verify_one(sct, cert_der, is_precert, keyring) {
+            Ok(()) => {
+                verified_logs.insert(sct.log_id);
+            }
+            Err(Error::CtLogNotTrusted { log_id }) => untrusted_log_id = Some(log_id),
+            Err(_) => {}
+        }
+    }
+    if verified_logs.len() < min_distinct_logs {
+        if let Some(log_id) = untrusted_log_id {
+            return Err(Error::CtLogNotTrusted { log_id });
+        }
+        return Err(Error::Verify {
+            reason: format!(
+                "only {} of {} required SCTs verified against distinct trusted logs",
+                verified_logs.len(),
+                min_distinct_logs
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POISON_OID_DER: [u8; 10] = [0x2b, 0x06, 0x01, 0x04, 0x01, 0xd6, 0x79, 0x02, 0x04, 0x03];
+    const EMBEDDED_SCT_LIST_OID_DER: [u8; 10] =
+        [0x2b, 0x06, 0x01, 0x04, 0x01, 0xd6, 0x79, 0x02, 0x04, 0x02];
+    const OTHER_OID_DER: [u8; 3] = [0x55, 0x1d, 0x0f];
+
+    fn encode_extension(oid_der: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut content = encode_tlv(0x06, oid_der); // extnID: OBJECT IDENTIFIER
+        content.extend_from_slice(&encode_tlv(0x04, value)); // extnValue: OCTET STRING
+        encode_tlv(DER_SEQUENCE, &content)
+    }
+
+    fn contains_subslice(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn strip_precert_extensions_removes_poison_and_sct_list() {
+        let poison_ext = encode_extension(&POISON_OID_DER, &[0x05, 0x00]);
+        let sct_list_ext = encode_extension(&EMBEDDED_SCT_LIST_OID_DER, &[0x04, 0x00]);
+        let kept_ext = encode_extension(&OTHER_OID_DER, &[0x01, 0x02]);
+
+        let extensions_seq = encode_tlv(
+            DER_SEQUENCE,
+            &[poison_ext, sct_list_ext, kept_ext].concat(),
+        );
+        let extensions_field = encode_tlv(EXTENSIONS_EXPLICIT_TAG, &extensions_seq);
+
+        // Stand-ins for the preceding TBSCertificate fields (version, serial, etc.) —
+        // opaque as far as this function is concerned.
+        let serial = encode_tlv(0x02, &[0x01]);
+        let tbs_content: Vec<u8> = [serial.clone(), extensions_field].concat();
+        let tbs_der = encode_tlv(DER_SEQUENCE, &tbs_content);
+
+        let stripped = strip_precert_extensions(&tbs_der).unwrap();
+
+        assert!(!contains_subslice(&stripped, &POISON_OID_DER));
+        assert!(!contains_subslice(&stripped, &EMBEDDED_SCT_LIST_OID_DER));
+        assert!(contains_subslice(&stripped, &OTHER_OID_DER));
+        assert!(contains_subslice(&stripped, &serial));
+    }
+
+    #[test]
+    fn strip_precert_extensions_is_noop_without_extensions_field() {
+        let serial = encode_tlv(0x02, &[0x01]);
+        let tbs_der = encode_tlv(DER_SEQUENCE, &serial);
+
+        let stripped = strip_precert_extensions(&tbs_der).unwrap();
+        assert_eq!(stripped, tbs_der);
+    }
+}
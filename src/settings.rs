@@ -0,0 +1,52 @@
+// Copyright 2024 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::{Error, Result, Stream, StreamAdapter};
+
+/// Loads verification/trust settings from a JSON or TOML document
+///
+/// The document format follows the c2pa SDK's settings schema (trust anchors,
+/// allowed certificate lists, hard binding requirements, remote manifest
+/// fetch policy, etc). Once loaded, the settings apply to every subsequent
+/// `Reader::from_stream` and `Builder::sign` call made by this process.
+pub fn load_settings(format: &str, json: &str) -> Result<()> {
+    c2pa::settings::load_settings_from_str(json, format).map_err(Error::from_c2pa_error)
+}
+
+/// Loads verification/trust settings from a stream rather than an in-memory string
+pub fn load_settings_from_stream(format: &str, stream: &dyn Stream) -> Result<()> {
+    let mut stream = StreamAdapter::from(stream);
+    let mut buf = Vec::new();
+    std::io::copy(&mut stream, &mut buf)?;
+    let json = String::from_utf8(buf).map_err(|e| Error::Decoding {
+        reason: e.to_string(),
+    })?;
+    load_settings(format, &json)
+}
+
+/// Sets the trust anchor certificates used to verify signing certificate chains
+///
+/// `pem` is one or more PEM-encoded certificates concatenated together.
+pub fn set_trust_anchors(pem: Vec<u8>) -> Result<()> {
+    let pem = String::from_utf8(pem).map_err(|e| Error::Decoding {
+        reason: e.to_string(),
+    })?;
+    c2pa::settings::set_setting("trust.trust_anchors", pem).map_err(Error::from_c2pa_error)?;
+    Ok(())
+}
+
+/// Sets whether `Reader::from_stream` should fetch remote manifests it encounters
+pub fn set_verify_remote_manifests(enabled: bool) -> Result<()> {
+    c2pa::settings::set_setting("verify.remote_manifest_fetch", enabled)
+        .map_err(Error::from_c2pa_error)?;
+    Ok(())
+}
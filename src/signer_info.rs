@@ -10,18 +10,17 @@
 // specific language governing permissions and limitations under
 // each license.
 
+use std::io::Read;
+use std::sync::RwLock;
+
 use c2pa::{Signer, SigningAlg, create_signer};
+use p256::ecdsa::signature::Signer as _;
 use serde::Deserialize;
+use sha1::Digest;
 
+use crate::callback_signer::SignerCallback;
 use crate::{Error, Result};
 
-
-/// Defines the callback interface for a signer
-pub trait SignerCallback: Send + Sync {
-    /// Read a stream of bytes from the stream
-    fn sign(&self, bytes: Vec<u8>) -> Result<Vec<u8>>;
-}
-
 /// Configuration for a Signer
 #[repr(C)]
 pub struct SignerConfig {
@@ -36,6 +35,33 @@ pub struct SignerConfig {
 
     /// Try to fetch OCSP response for the signing cert if available
     pub use_ocsp: bool,
+
+    /// Overrides the OCSP responder URL instead of reading it from the signing
+    /// cert's Authority Information Access extension
+    pub ocsp_responder_url: Option<String>,
+
+    /// Timeout in seconds for the OCSP HTTP request; defaults to 10 seconds
+    pub ocsp_timeout_secs: Option<u64>,
+
+    /// Signed Certificate Timestamps to embed in the COSE unsigned attributes,
+    /// proving the signing cert's inclusion in one or more CT logs. Not needed
+    /// when the cert already carries SCTs embedded by its issuing CA.
+    pub scts: Vec<Vec<u8>>,
+
+    /// PEM-encoded trust anchor bundle the signing chain must chain to. When
+    /// `None`, the chain-to-anchor check is skipped.
+    pub trust_anchors: Option<Vec<u8>>,
+
+    /// Extended Key Usage OIDs (dotted-decimal, e.g. `1.3.6.1.5.5.7.3.36` for
+    /// document signing) the end-entity cert must carry. When empty, the EKU
+    /// check is skipped.
+    pub allowed_eku_oids: Vec<String>,
+
+    /// Overrides the computed `reserve_size` estimate
+    ///
+    /// Useful for remote signers that cannot introspect their own cert chain to
+    /// size the reservation accurately.
+    pub reserve_size_override: Option<u32>,
 }
 
 /// SignerInfo provides the information needed to create a signer
@@ -97,6 +123,13 @@ pub struct CallbackSigner {
     alg: SigningAlg,
     sign_certs: Vec<u8>,
     ta_url: Option<String>,
+    use_ocsp: bool,
+    ocsp_responder_url: Option<String>,
+    ocsp_timeout_secs: u64,
+    scts: Vec<Vec<u8>>,
+    trust_anchors: Option<Vec<u8>>,
+    allowed_eku_oids: Vec<String>,
+    reserve_size_override: Option<u32>,
 }
 
 impl CallbackSigner {
@@ -112,12 +145,70 @@ impl CallbackSigner {
             alg: config.alg,
             sign_certs: config.certs,
             ta_url: config.time_authority_url,
+            use_ocsp: config.use_ocsp,
+            ocsp_responder_url: config.ocsp_responder_url,
+            ocsp_timeout_secs: config.ocsp_timeout_secs.unwrap_or(10),
+            scts: config.scts,
+            trust_anchors: config.trust_anchors,
+            allowed_eku_oids: config.allowed_eku_oids,
+            reserve_size_override: config.reserve_size_override,
         }
     }
+
+    /// Fetches an OCSP response for the end-entity signing cert, to be stapled into
+    /// the COSE signature's unsigned header bucket
+    ///
+    /// The responder URL is taken from `ocsp_responder_url` if set, otherwise read
+    /// from the cert's Authority Information Access extension. Any failure (no
+    /// responder found, no issuer cert in the chain, network or HTTP error)
+    /// degrades gracefully to `None` so signing still proceeds without the
+    /// staple. The response body is returned as received; it is not parsed.
+    fn fetch_ocsp_response(&self) -> Option<Vec<u8>> {
+        let leaf_der = pem::parse_many(&self.sign_certs)
+            .ok()?
+            .into_iter()
+            .next()?
+            .into_contents();
+        let (_, leaf) = x509_parser::parse_x509_certificate(&leaf_der).ok()?;
+
+        let responder_url = match &self.ocsp_responder_url {
+            Some(url) => url.clone(),
+            None => ocsp_responder_url_from_aia(&leaf)?,
+        };
+
+        let issuer_der = pem::parse_many(&self.sign_certs)
+            .ok()?
+            .into_iter()
+            .nth(1)?
+            .into_contents();
+        let (_, issuer) = x509_parser::parse_x509_certificate(&issuer_der).ok()?;
+
+        let ocsp_request = build_ocsp_request(&leaf, &issuer).ok()?;
+
+        let response = ureq::post(&responder_url)
+            .set("Content-Type", "application/ocsp-request")
+            .timeout(std::time::Duration::from_secs(self.ocsp_timeout_secs))
+            .send_bytes(&ocsp_request)
+            .ok()?;
+
+        let mut der = Vec::new();
+        response.into_reader().read_to_end(&mut der).ok()?;
+        Some(der)
+    }
 }
 
 impl Signer for CallbackSigner {
     fn sign(&self, data: &[u8]) -> c2pa::Result<Vec<u8>> {
+        if !self.allowed_eku_oids.is_empty() || self.trust_anchors.is_some() {
+            let chain = self.certs()?;
+            crate::cert_policy::validate_signing_chain(
+                &chain,
+                self.trust_anchors.as_deref(),
+                &self.allowed_eku_oids,
+            )
+            .map_err(|e| c2pa::Error::BadParam(e.to_string()))?;
+        }
+
         self.callback
             .sign(data.to_vec())
             .map_err(|e| c2pa::Error::BadParam(e.to_string()))
@@ -134,10 +225,268 @@ impl Signer for CallbackSigner {
     }
 
     fn reserve_size(&self) -> usize {
-        20000
+        if let Some(override_size) = self.reserve_size_override {
+            return override_size as usize;
+        }
+
+        let mut size = crate::reserve_size::signature_len(self.alg)
+            + crate::reserve_size::COSE_STRUCTURAL_OVERHEAD;
+        size += self
+            .certs()
+            .map(|certs| certs.iter().map(Vec::len).sum::<usize>())
+            .unwrap_or(0);
+        if self.ta_url.is_some() {
+            size += TIMESTAMP_TOKEN_BOUND;
+        }
+        if self.use_ocsp {
+            size += OCSP_RESPONSE_BOUND;
+        }
+        size
     }
 
     fn time_authority_url(&self) -> Option<String> {
         self.ta_url.clone()
     }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        if !self.use_ocsp {
+            return None;
+        }
+        self.fetch_ocsp_response()
+    }
+
+    fn sct_vals(&self) -> Option<Vec<Vec<u8>>> {
+        if self.scts.is_empty() {
+            None
+        } else {
+            Some(self.scts.clone())
+        }
+    }
+}
+
+/// Upper bound on the size of an RFC 3161 timestamp token
+const TIMESTAMP_TOKEN_BOUND: usize = 10_000;
+/// Upper bound on the size of a stapled DER-encoded OCSP response
+const OCSP_RESPONSE_BOUND: usize = 6_000;
+
+/// Reads the OCSP responder URL out of a cert's Authority Information Access extension
+fn ocsp_responder_url_from_aia(cert: &x509_parser::certificate::X509Certificate) -> Option<String> {
+    let aia = cert.authority_info_access()?;
+    aia.ocsp_uris().next().map(|uri| uri.to_string())
+}
+
+/// DER encoding of the SHA-1 `AlgorithmIdentifier.algorithm` OID (1.3.14.3.2.26),
+/// the hash OCSP's `CertID` conventionally uses
+const SHA1_ALGORITHM_OID: [u8; 5] = [0x2b, 0x0e, 0x03, 0x02, 0x1a];
+
+/// Builds a DER-encoded OCSP request (RFC 6960) for `leaf`, issued against `issuer`
+///
+/// Built directly via [`crate::sct::encode_tlv`] rather than pulled in from a
+/// full ASN.1 OCSP crate, since this request only ever needs the single-cert,
+/// unsigned form (no `version`, `requestorName`, or `optionalSignature`, all of
+/// which are optional/defaulted).
+fn build_ocsp_request(
+    leaf: &x509_parser::certificate::X509Certificate,
+    issuer: &x509_parser::certificate::X509Certificate,
+) -> Result<Vec<u8>> {
+    use crate::sct::encode_tlv;
+
+    let issuer_name_hash = sha1::Sha1::digest(issuer.subject().as_raw());
+    let issuer_key_hash = sha1::Sha1::digest(issuer.public_key().subject_public_key.as_ref());
+    let serial = leaf.raw_serial();
+
+    // AlgorithmIdentifier ::= SEQUENCE { algorithm OBJECT IDENTIFIER, parameters NULL }
+    let hash_algorithm = encode_tlv(
+        0x30,
+        &[encode_tlv(0x06, &SHA1_ALGORITHM_OID), encode_tlv(0x05, &[])].concat(),
+    );
+
+    // CertID ::= SEQUENCE { hashAlgorithm, issuerNameHash, issuerKeyHash, serialNumber }
+    let cert_id = encode_tlv(
+        0x30,
+        &[
+            hash_algorithm,
+            encode_tlv(0x04, &issuer_name_hash),
+            encode_tlv(0x04, &issuer_key_hash),
+            encode_tlv(0x02, serial),
+        ]
+        .concat(),
+    );
+
+    // Request ::= SEQUENCE { reqCert CertID }
+    let request = encode_tlv(0x30, &cert_id);
+
+    // TBSRequest ::= SEQUENCE { requestList SEQUENCE OF Request }
+    let request_list = encode_tlv(0x30, &request);
+    let tbs_request = encode_tlv(0x30, &request_list);
+
+    // OCSPRequest ::= SEQUENCE { tbsRequest TBSRequest }
+    Ok(encode_tlv(0x30, &tbs_request))
+}
+
+/// Configuration for a [`SigstoreSigner`]
+///
+/// Analogous to [`SignerConfig`], but points at a keyless-signing ceremony
+/// instead of a local cert/key pair.
+#[derive(Clone, Debug)]
+pub struct SigstoreConfig {
+    /// Fulcio-style CA endpoint that exchanges an OIDC token and an ephemeral
+    /// public key for a short-lived signing certificate chain
+    pub fulcio_url: String,
+    /// Rekor-style transparency log endpoint the signature is submitted to
+    pub rekor_url: String,
+    /// OIDC issuer used to obtain the identity token (interactive or ambient/CI)
+    pub oidc_issuer_url: String,
+    /// Signing algorithm to report to the c2pa SDK; the ephemeral key is always P-256
+    pub alg: SigningAlg,
+}
+
+/// The transparency log entry recorded when a signature is submitted to Rekor
+#[derive(Clone, Debug, Default)]
+pub struct TransparencyLogEntry {
+    pub log_index: i64,
+    pub inclusion_proof: Vec<u8>,
+    pub signed_entry_timestamp: Vec<u8>,
+}
+
+/// Keyless signer backed by a Sigstore-style ceremony
+///
+/// Constructed much like [`SignerInfo::signer`], but instead of reading a
+/// long-lived private key from disk, it binds an ephemeral P-256 keypair to
+/// an OIDC identity via a Fulcio-style CA and logs the resulting signature
+/// to a Rekor-style transparency log. Suitable for ephemeral CI environments
+/// that have no signing cert to manage.
+pub struct SigstoreSigner {
+    config: SigstoreConfig,
+    ephemeral_key: p256::ecdsa::SigningKey,
+    cert_chain: Vec<u8>,
+    log_entry: RwLock<Option<TransparencyLogEntry>>,
+}
+
+impl SigstoreSigner {
+    /// Runs the keyless signing ceremony: generates an ephemeral keypair, proves
+    /// possession of it to the CA alongside the caller-supplied OIDC identity
+    /// token, and stores the short-lived signing certificate chain the CA returns.
+    ///
+    /// `oidc_token` is obtained by the caller beforehand, either interactively
+    /// (browser-based OIDC flow) or from an ambient/CI token source.
+    pub fn new(config: SigstoreConfig, oidc_token: &str) -> Result<Self> {
+        let ephemeral_key = p256::ecdsa::SigningKey::random(&mut rand::thread_rng());
+        let public_key_der = ephemeral_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec();
+
+        // Proof of possession: sign the OIDC token with the ephemeral key so the
+        // CA can confirm this key belongs to whoever holds the identity token.
+        let proof_of_possession: p256::ecdsa::Signature =
+            ephemeral_key.sign(oidc_token.as_bytes());
+
+        let request = serde_json::json!({
+            "publicKey": {
+                "content": base64_encode(&public_key_der),
+                "algorithm": "ecdsa",
+            },
+            "signedEmailAddress": base64_encode(proof_of_possession.to_der().as_bytes()),
+            "credentials": { "oidcIdentityToken": oidc_token },
+        });
+
+        let response = ureq::post(&config.fulcio_url)
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string())
+            .map_err(|e| Error::Other {
+                reason: format!("Fulcio request failed: {e}"),
+            })?;
+
+        let cert_chain = response
+            .into_string()
+            .map_err(|e| Error::Io {
+                reason: e.to_string(),
+            })?
+            .into_bytes();
+
+        Ok(Self {
+            config,
+            ephemeral_key,
+            cert_chain,
+            log_entry: RwLock::new(None),
+        })
+    }
+
+    /// Submits a signature and its certificate to the transparency log, recording
+    /// the returned log index, inclusion proof, and signed entry timestamp
+    fn log_to_rekor(&self, signature: &[u8]) -> Result<TransparencyLogEntry> {
+        let request = serde_json::json!({
+            "kind": "hashedrekord",
+            "apiVersion": "0.0.1",
+            "spec": {
+                "signature": { "content": base64_encode(signature) },
+                "publicKey": { "content": base64_encode(&self.cert_chain) },
+            },
+        });
+
+        let response = ureq::post(&self.config.rekor_url)
+            .set("Content-Type", "application/json")
+            .send_string(&request.to_string())
+            .map_err(|e| Error::Other {
+                reason: format!("Rekor request failed: {e}"),
+            })?;
+
+        let body: serde_json::Value = response.into_json().map_err(|e| Error::Json {
+            reason: e.to_string(),
+        })?;
+
+        Ok(TransparencyLogEntry {
+            log_index: body["logIndex"].as_i64().unwrap_or_default(),
+            inclusion_proof: body["verification"]["inclusionProof"].to_string().into_bytes(),
+            signed_entry_timestamp: body["verification"]["signedEntryTimestamp"]
+                .as_str()
+                .unwrap_or_default()
+                .as_bytes()
+                .to_vec(),
+        })
+    }
+
+    /// The transparency log entry recorded for the most recent signature, if any
+    pub fn transparency_log_entry(&self) -> Option<TransparencyLogEntry> {
+        self.log_entry.try_read().ok().and_then(|entry| entry.clone())
+    }
+}
+
+impl Signer for SigstoreSigner {
+    fn sign(&self, data: &[u8]) -> c2pa::Result<Vec<u8>> {
+        let signature: p256::ecdsa::Signature = self.ephemeral_key.sign(data);
+        let signature = signature.to_der().as_bytes().to_vec();
+
+        let entry = self.log_to_rekor(&signature).ok();
+        if let Ok(mut slot) = self.log_entry.try_write() {
+            *slot = entry;
+        }
+
+        Ok(signature)
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.config.alg
+    }
+
+    fn certs(&self) -> c2pa::Result<Vec<Vec<u8>>> {
+        let mut pems =
+            pem::parse_many(&self.cert_chain).map_err(|e| c2pa::Error::OtherError(Box::new(e)))?;
+        Ok(pems.drain(..).map(|p| p.into_contents()).collect())
+    }
+
+    fn reserve_size(&self) -> usize {
+        20000
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        None
+    }
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(data)
 }